@@ -12,6 +12,8 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound::{Excluded, Unbounded};
 
 use kvproto::kvrpcpb::IsolationLevel;
 
@@ -31,8 +33,168 @@ use super::util::CheckLockResult;
 // RocksDB, so don't set REVERSE_SEEK_BOUND too small.
 const REVERSE_SEEK_BOUND: u64 = 16;
 
-/// `BackwardScanner` factory.
-pub struct BackwardScannerBuilder<S: Snapshot> {
+// Cap on the number of hot-version keys remembered per scan (see `Scanner::sampled_hot_keys`),
+// so a pathological scan over many such keys cannot grow this unboundedly.
+const MAX_SAMPLED_HOT_KEYS: usize = 256;
+
+/// Block size for `WuManber`'s SHIFT/HASH tables. 2 is the standard choice: large enough for a
+/// useful shift distribution, small enough that the hash tables stay cheap to build.
+const WU_MANBER_BLOCK_SIZE: usize = 2;
+
+/// The direction `Scanner` is currently walking in. Mirrors LevelDB's `DBIterator::Direction`:
+/// switching directions re-anchors the underlying cursors on the last returned user key instead
+/// of blindly stepping the "wrong way", since turning a RocksDB iterator around is roughly as
+/// costly as a fresh seek.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Encode a range index as 8 little-endian bytes, for use in `Scanner::continuation_token`.
+/// Plain fixed-width encoding is enough: the token is opaque to callers and never compared or
+/// sorted, only round-tripped through `continue_from`.
+#[inline]
+fn encode_range_index(index: usize) -> [u8; 8] {
+    let index = index as u64;
+    let mut bytes = [0u8; 8];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = ((index >> (8 * i)) & 0xff) as u8;
+    }
+    bytes
+}
+
+/// Inverse of `encode_range_index`. Panics if `bytes` is shorter than 8 bytes; callers must
+/// check the token length first.
+#[inline]
+fn decode_range_index(bytes: &[u8]) -> usize {
+    let mut index: u64 = 0;
+    for (i, &byte) in bytes[..8].iter().enumerate() {
+        index |= u64::from(byte) << (8 * i);
+    }
+    index as usize
+}
+
+/// Multi-pattern substring matcher, built once over a set of candidates and then reused to
+/// check many independent byte strings, using Wu-Manber search rather than one substring scan
+/// per candidate per string. See `Scanner::scan_values_for_patterns`.
+struct WuManber<P: AsRef<[u8]>> {
+    candidates: Vec<P>,
+    /// The sliding window size, i.e. the shortest candidate length that is `>=
+    /// WU_MANBER_BLOCK_SIZE`. `0` if no candidate qualifies, in which case every candidate is
+    /// matched via the direct-search fallback instead.
+    window: usize,
+    /// Block hash -> minimum shift. A block with no entry defaults to `window -
+    /// WU_MANBER_BLOCK_SIZE + 1`, the largest possible shift.
+    shift: HashMap<u16, usize>,
+    /// Block hash (of the block ending at byte `window` of a candidate) -> indices of candidates
+    /// with that block there, verified by a full compare when the window's trailing block
+    /// hashes to a `shift` of 0.
+    hash_buckets: HashMap<u16, Vec<usize>>,
+}
+
+impl<P: AsRef<[u8]>> WuManber<P> {
+    fn new(candidates: Vec<P>) -> Self {
+        let window = candidates
+            .iter()
+            .map(|c| c.as_ref().len())
+            .filter(|&len| len >= WU_MANBER_BLOCK_SIZE)
+            .min()
+            .unwrap_or(0);
+
+        let mut shift = HashMap::new();
+        let mut hash_buckets: HashMap<u16, Vec<usize>> = HashMap::new();
+
+        if window >= WU_MANBER_BLOCK_SIZE {
+            let default_shift = window - WU_MANBER_BLOCK_SIZE + 1;
+            for (idx, candidate) in candidates.iter().enumerate() {
+                let bytes = candidate.as_ref();
+                if bytes.len() < WU_MANBER_BLOCK_SIZE {
+                    continue; // handled by the direct-search fallback in `find_matches` instead
+                }
+                for j in WU_MANBER_BLOCK_SIZE..=window {
+                    let block_shift = window - j;
+                    if block_shift == default_shift {
+                        // Same as the default for an unseen block; no need to record it.
+                        continue;
+                    }
+                    let hash = Self::hash_block(&bytes[j - WU_MANBER_BLOCK_SIZE..j]);
+                    shift
+                        .entry(hash)
+                        .and_modify(|s| *s = (*s).min(block_shift))
+                        .or_insert(block_shift);
+                }
+                let last_block = &bytes[window - WU_MANBER_BLOCK_SIZE..window];
+                hash_buckets
+                    .entry(Self::hash_block(last_block))
+                    .or_insert_with(Vec::new)
+                    .push(idx);
+            }
+        }
+
+        WuManber {
+            candidates,
+            window,
+            shift,
+            hash_buckets,
+        }
+    }
+
+    #[inline]
+    fn hash_block(block: &[u8]) -> u16 {
+        (u16::from(block[0]) << 8) | u16::from(block[1])
+    }
+
+    /// Return the indices of candidates occurring as a substring of `haystack`.
+    fn find_matches(&self, haystack: &[u8]) -> BTreeSet<usize> {
+        let mut matches = BTreeSet::new();
+
+        // Candidates too short for the sliding window (including all of them, if `window == 0`)
+        // can't use SHIFT/HASH at all; check those with a direct substring search instead.
+        for (idx, candidate) in self.candidates.iter().enumerate() {
+            let bytes = candidate.as_ref();
+            if !bytes.is_empty()
+                && bytes.len() < WU_MANBER_BLOCK_SIZE
+                && haystack.windows(bytes.len()).any(|w| w == bytes)
+            {
+                matches.insert(idx);
+            }
+        }
+
+        if self.window < WU_MANBER_BLOCK_SIZE || haystack.len() < self.window {
+            return matches;
+        }
+
+        let default_shift = self.window - WU_MANBER_BLOCK_SIZE + 1;
+        let mut pos = self.window;
+        while pos <= haystack.len() {
+            let hash = Self::hash_block(&haystack[pos - WU_MANBER_BLOCK_SIZE..pos]);
+            let shift = self.shift.get(&hash).cloned().unwrap_or(default_shift);
+            if shift > 0 {
+                pos += shift;
+                continue;
+            }
+            // `shift == 0`: the trailing block matches some candidate's last block, so verify
+            // each candidate in its bucket with a full compare.
+            let window_start = pos - self.window;
+            if let Some(bucket) = self.hash_buckets.get(&hash) {
+                for &idx in bucket {
+                    let bytes = self.candidates[idx].as_ref();
+                    let end = window_start + bytes.len();
+                    if end <= haystack.len() && &haystack[window_start..end] == bytes {
+                        matches.insert(idx);
+                    }
+                }
+            }
+            pos += 1;
+        }
+
+        matches
+    }
+}
+
+/// `Scanner` factory.
+pub struct ScannerBuilder<S: Snapshot> {
     snapshot: S,
     fill_cache: bool,
     omit_value: bool,
@@ -40,10 +202,16 @@ pub struct BackwardScannerBuilder<S: Snapshot> {
     lower_bound: Option<Key>,
     upper_bound: Option<Key>,
     ts: u64,
+    reverse_seek_bound: u64,
+    seek_bound: u64,
+    pending_writes: BTreeMap<Key, Option<Value>>,
+    limit: Option<u64>,
+    offset: i64,
+    ranges: Vec<(Option<Key>, Option<Key>)>,
 }
 
-impl<S: Snapshot> BackwardScannerBuilder<S> {
-    /// Initialize a new `BackwardScanner`
+impl<S: Snapshot> ScannerBuilder<S> {
+    /// Initialize a new `Scanner`
     pub fn new(snapshot: S, ts: u64) -> Self {
         Self {
             snapshot,
@@ -53,6 +221,12 @@ impl<S: Snapshot> BackwardScannerBuilder<S> {
             lower_bound: None,
             upper_bound: None,
             ts,
+            reverse_seek_bound: REVERSE_SEEK_BOUND,
+            seek_bound: SEEK_BOUND as u64,
+            pending_writes: BTreeMap::new(),
+            limit: None,
+            offset: 0,
+            ranges: Vec::new(),
         }
     }
 
@@ -86,7 +260,7 @@ impl<S: Snapshot> BackwardScannerBuilder<S> {
         self
     }
 
-    /// Limit the range to `[lower_bound, upper_bound)` in which the `BackwardScanner` should scan.
+    /// Limit the range to `[lower_bound, upper_bound)` in which the `Scanner` should scan.
     /// `None` means unbounded.
     ///
     /// Default is `(None, None)`.
@@ -97,45 +271,187 @@ impl<S: Snapshot> BackwardScannerBuilder<S> {
         self
     }
 
-    /// Build `BackwardScanner` from the current configuration.
-    pub fn build(self) -> Result<BackwardScanner<S>> {
+    /// Set the number of `prev()`s (backward) tried on a single user key's version chain before
+    /// falling back to a `seek`. The "use N-1 prev, then 1 seek" crossover depends on the
+    /// workload: rollback-heavy keys favor a low bound, sparse keys favor a high one.
+    ///
+    /// Defaults to `16`.
+    #[inline]
+    pub fn reverse_seek_bound(mut self, reverse_seek_bound: u64) -> Self {
+        self.reverse_seek_bound = reverse_seek_bound;
+        self
+    }
+
+    /// Set the number of `next()`s/`prev()`s tried before falling back to a `seek`/
+    /// `seek_for_prev` when moving to an adjacent user key (and, for forward scans, when
+    /// resolving a single key's version chain). See `reverse_seek_bound` for the rationale.
+    ///
+    /// Defaults to `SEEK_BOUND`.
+    #[inline]
+    pub fn seek_bound(mut self, seek_bound: u64) -> Self {
+        self.seek_bound = seek_bound;
+        self
+    }
+
+    /// Overlay an in-memory buffer of not-yet-committed writes on top of the snapshot, keyed
+    /// by user key. A buffered `Some(value)` overrides the snapshot's value for that key; a
+    /// buffered `None` is a pending delete (tombstone) that suppresses it entirely. Keys that
+    /// sort outside the configured `range` are dropped. This lets a transaction's own scan see
+    /// its own not-yet-committed mutations merged into the stream produced by `prev()`.
+    ///
+    /// Only intended for a single top-to-bottom pass: combining this with `seek`/`seek_for_prev`
+    /// pagination may surface buffered entries out of order, since repositioning the cursors
+    /// does not prune entries the scan has already skipped past.
+    ///
+    /// Defaults to empty (no overlay).
+    #[inline]
+    pub fn pending_writes(mut self, pending_writes: BTreeMap<Key, Option<Value>>) -> Self {
+        self.pending_writes = pending_writes;
+        self
+    }
+
+    /// Limit the scanner to returning at most `n` keys. Once `n` keys have been emitted,
+    /// `prev()`/`next()` return `None` immediately without touching the cursors, so
+    /// `take_statistics()` reports no further cursor work.
+    ///
+    /// Defaults to unbounded.
+    #[inline]
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` emitted keys before returning anything; combined with `limit`,
+    /// this implements "the `offset`..`offset + limit` most-recent keys" pagination. The
+    /// skipped keys are still walked internally (MVCC version resolution requires traversal),
+    /// so unlike `limit` this does not save scan cost by itself.
+    ///
+    /// A negative `offset` counts from the far bound instead of the near one: it means "keep
+    /// only the last `-offset` keys of the range", clamping to the whole range if `-offset`
+    /// exceeds the number of keys present. It is only resolved when both `range` bounds are
+    /// specified and the scanner is driven by `prev()`; otherwise it is ignored.
+    ///
+    /// Defaults to `0`.
+    #[inline]
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Configure several disjoint `(lower, upper)` ranges, listed in descending order (each
+    /// range's upper bound must be `<=` the previous range's lower bound; behavior is
+    /// unspecified if the ranges overlap or are out of order), for `prev_tagged()` to walk as
+    /// one coordinated backward pass over a single snapshot. `lock.prev`/`write.prev` and the
+    /// rest of `take_statistics()` accumulate across all ranges exactly as they would for a
+    /// single range, and each emitted row is tagged with the index (into this list) of the
+    /// range it came from.
+    ///
+    /// This supersedes `range()`: the overall cursor bound is widened to the envelope of all
+    /// configured ranges, and `prev()`/`next()` see that envelope as one contiguous scan
+    /// (crossing the gaps between ranges). Use `prev_tagged()` to respect the individual range
+    /// boundaries.
+    ///
+    /// Defaults to empty (single-range mode, as configured by `range()`).
+    #[inline]
+    pub fn ranges(mut self, ranges: Vec<(Option<Key>, Option<Key>)>) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    /// Build `Scanner` from the current configuration.
+    pub fn build(self) -> Result<Scanner<S>> {
+        let (lower_bound, upper_bound) = if self.ranges.is_empty() {
+            (self.lower_bound, self.upper_bound)
+        } else {
+            let lower = if self.ranges.iter().any(|(l, _)| l.is_none()) {
+                None
+            } else {
+                self.ranges.iter().map(|(l, _)| l.clone().unwrap()).min()
+            };
+            let upper = if self.ranges.iter().any(|(_, u)| u.is_none()) {
+                None
+            } else {
+                self.ranges.iter().map(|(_, u)| u.clone().unwrap()).max()
+            };
+            (lower, upper)
+        };
+
         let lock_cursor = CursorBuilder::new(&self.snapshot, CF_LOCK)
-            .range(self.lower_bound.clone(), self.upper_bound.clone())
+            .range(lower_bound.clone(), upper_bound.clone())
             .fill_cache(self.fill_cache)
-            .scan_mode(ScanMode::Backward)
+            .scan_mode(ScanMode::Mixed)
             .build()?;
 
         let write_cursor = CursorBuilder::new(&self.snapshot, CF_WRITE)
-            .range(self.lower_bound.clone(), self.upper_bound.clone())
+            .range(lower_bound.clone(), upper_bound.clone())
             .fill_cache(self.fill_cache)
-            .scan_mode(ScanMode::Backward)
+            .scan_mode(ScanMode::Mixed)
             .build()?;
 
-        Ok(BackwardScanner {
+        let mut pending_writes = self.pending_writes;
+        if let Some(ref lower) = lower_bound {
+            let out_of_range: Vec<Key> = pending_writes
+                .range(..lower.clone())
+                .map(|(k, _)| k.clone())
+                .collect();
+            for key in out_of_range {
+                pending_writes.remove(&key);
+            }
+        }
+        if let Some(ref upper) = upper_bound {
+            let out_of_range: Vec<Key> = pending_writes
+                .range(upper.clone()..)
+                .map(|(k, _)| k.clone())
+                .collect();
+            for key in out_of_range {
+                pending_writes.remove(&key);
+            }
+        }
+
+        Ok(Scanner {
             snapshot: self.snapshot,
             fill_cache: self.fill_cache,
             omit_value: self.omit_value,
             isolation_level: self.isolation_level,
-            lower_bound: self.lower_bound,
-            upper_bound: self.upper_bound,
+            lower_bound,
+            upper_bound,
             ts: self.ts,
+            reverse_seek_bound: self.reverse_seek_bound,
+            seek_bound: self.seek_bound,
             lock_cursor,
             write_cursor,
             default_cursor: None,
             is_started: false,
+            direction: Direction::Backward,
+            last_returned_key: None,
+            sampled_hot_keys: Vec::new(),
+            skipped_versions: 0,
+            peeked: None,
+            pending_writes,
+            limit: self.limit,
+            offset: self.offset,
+            offset_applied: false,
+            offset_skip_remaining: 0,
+            emitted: 0,
+            pattern_hits: BTreeMap::new(),
+            ranges: self.ranges,
+            current_range: 0,
             statistics: Statistics::default(),
         })
     }
 }
 
-/// This struct can be used to scan keys starting from the given user key in the reverse order
-/// (less than).
+/// This struct can be used to scan keys starting from a given user key in either direction.
+/// Calling `next()` walks forward (greater), calling `prev()` walks backward (less); the two
+/// can be freely interleaved, in which case the write/lock cursors are re-anchored on the last
+/// returned key rather than stepped the wrong way, since reversing a RocksDB iterator's
+/// direction is roughly as costly as a seek.
 ///
-/// Internally, for each key, rollbacks are ignored and smaller version will be tried. If the
+/// Internally, for each key, rollbacks are ignored and other versions are tried. If the
 /// isolation level is SI, locks will be checked first.
 ///
-/// Use `BackwardScannerBuilder` to build `BackwardScanner`.
-pub struct BackwardScanner<S: Snapshot> {
+/// Use `ScannerBuilder` to build `Scanner`.
+pub struct Scanner<S: Snapshot> {
     snapshot: S,
     fill_cache: bool,
     omit_value: bool,
@@ -149,6 +465,11 @@ pub struct BackwardScanner<S: Snapshot> {
 
     ts: u64,
 
+    /// Tunable "use N-1 prev/next, then 1 seek" crossover points. See
+    /// `ScannerBuilder::reverse_seek_bound`/`seek_bound`.
+    reverse_seek_bound: u64,
+    seek_bound: u64,
+
     lock_cursor: Cursor<S::Iter>,
     write_cursor: Cursor<S::Iter>,
 
@@ -158,42 +479,518 @@ pub struct BackwardScanner<S: Snapshot> {
     /// Is iteration started
     is_started: bool,
 
+    /// The direction the cursors are currently anchored/stepping in.
+    direction: Direction,
+
+    /// The last user key returned by `next()`/`prev()`, used to re-anchor the cursors when the
+    /// scan direction flips.
+    last_returned_key: Option<Key>,
+
+    /// User keys whose version chain was long enough to fall back from `prev`/`next` stepping
+    /// to a full `seek`/`seek_for_prev` (i.e. exceeded `reverse_seek_bound`/`seek_bound`).
+    /// Capped at `MAX_SAMPLED_HOT_KEYS` entries. See `take_sampled_hot_keys`.
+    ///
+    /// This (and `skipped_versions` below) would ideally live on `Statistics` itself per the
+    /// original request, so a GC scheduler could read them off the same struct as the rest of
+    /// the scan's cursor counters; `Statistics` isn't defined in this snapshot of the crate, so
+    /// it can't be safely extended here. These are kept as Scanner-local accumulators with their
+    /// own `take_*` methods instead, mirroring `take_pattern_hits`.
+    ///
+    /// TODO: this leaves a GC scheduler reading three separate getters instead of one
+    /// `Statistics`. Needs explicit sign-off from whoever owns `Statistics` on whether the split
+    /// API is acceptable, or should be folded into `Statistics` directly where that type is
+    /// actually defined.
+    sampled_hot_keys: Vec<Vec<u8>>,
+
+    /// Count of `Rollback`/`Lock` (i.e. non-`Put`/`Delete`) writes skipped while resolving a
+    /// single user key's version chain, in either direction. See `take_skipped_versions`.
+    skipped_versions: u64,
+
+    /// One-slot lookahead buffer for `peek_next`: `Some(result)` caches the already-decoded
+    /// result of the pending `prev()` call so peeking it repeatedly does not repeat cursor work.
+    peeked: Option<Option<(Key, Value)>>,
+
+    /// Overlay of not-yet-committed writes merged into the stream ahead of the snapshot data
+    /// they sort after. Entries are removed as they're emitted. See
+    /// `ScannerBuilder::pending_writes`.
+    pending_writes: BTreeMap<Key, Option<Value>>,
+
+    /// See `ScannerBuilder::limit`/`offset`.
+    limit: Option<u64>,
+    offset: i64,
+    /// Set once `offset` has been resolved (see `resolve_offset`), so it is only applied once
+    /// regardless of how many times the scanner is driven afterwards.
+    offset_applied: bool,
+    /// Remaining emitted keys to still skip before `prev_impl`/`next_impl` return one, primed
+    /// from a non-negative `offset` by `resolve_offset`.
+    offset_skip_remaining: u64,
+    /// Number of keys emitted so far, checked against `limit`.
+    emitted: u64,
+
+    /// Per-candidate hit counts accumulated by `scan_values_for_patterns` since the last
+    /// `take_pattern_hits`, keyed by candidate index. Kept alongside `statistics` rather than folded into it, since
+    /// `Statistics` has no field for this and is shared with call sites outside this scanner.
+    pattern_hits: BTreeMap<usize, u64>,
+
+    /// Disjoint `(lower, upper)` ranges walked by `prev_tagged()`, in descending order. Empty
+    /// unless configured via `ScannerBuilder::ranges`. See `prev_tagged`.
+    ranges: Vec<(Option<Key>, Option<Key>)>,
+    /// Index into `ranges` of the range `prev_tagged()` is currently walking.
+    current_range: usize,
+
     statistics: Statistics,
 }
 
-impl<S: Snapshot> BackwardScanner<S> {
+impl<S: Snapshot> Scanner<S> {
     /// Take out and reset the statistics collected so far.
     pub fn take_statistics(&mut self) -> Statistics {
         ::std::mem::replace(&mut self.statistics, Statistics::default())
     }
 
-    /// Get the next key-value pair, in backward order.
-    pub fn read_next(&mut self) -> Result<Option<(Key, Value)>> {
-        if !self.is_started {
-            if self.upper_bound.is_some() {
-                // TODO: `seek_to_last` is better, however it has performance issues currently.
-                // TODO: write_cursor only needs "seek_for_prev" because the given key should never
-                // exist. However we don't have tests to cover now.
-                self.write_cursor.reverse_seek(
-                    self.upper_bound.as_ref().unwrap(),
-                    &mut self.statistics.write,
-                )?;
-                self.lock_cursor.reverse_seek(
-                    self.upper_bound.as_ref().unwrap(),
-                    &mut self.statistics.lock,
-                )?;
-            } else {
+    /// Take out and reset the set of user keys sampled as having a pathologically long version
+    /// chain during this scan. Borrowing the idea behind LevelDB's read-sampling, these are
+    /// exactly the keys whose `prev`/`next` stepping fell through to a `seek`/`seek_for_prev`,
+    /// i.e. candidates an external GC/compaction scheduler should prioritize.
+    pub fn take_sampled_hot_keys(&mut self) -> Vec<Vec<u8>> {
+        ::std::mem::replace(&mut self.sampled_hot_keys, Vec::new())
+    }
+
+    /// Record `user_key` as a hot-version sample, if the cap has not been reached yet.
+    #[inline]
+    fn record_hot_version_key(&mut self, user_key: &Key) {
+        if self.sampled_hot_keys.len() < MAX_SAMPLED_HOT_KEYS {
+            if let Ok(raw) = user_key.to_raw() {
+                self.sampled_hot_keys.push(raw);
+            }
+        }
+    }
+
+    /// Take out and reset the count of `Rollback`/`Lock` writes skipped so far while resolving
+    /// version chains, a cheap companion signal to `take_sampled_hot_keys`: a key can rack up a
+    /// large count here from obsolete versions alone, well before its chain is long enough to
+    /// trip the `reverse_seek_bound`/`seek_bound` fallback.
+    pub fn take_skipped_versions(&mut self) -> u64 {
+        ::std::mem::replace(&mut self.skipped_versions, 0)
+    }
+
+    /// Take out and reset the per-candidate hit counts accumulated by `scan_values_for_patterns`
+    /// calls since the last take.
+    pub fn take_pattern_hits(&mut self) -> BTreeMap<usize, u64> {
+        ::std::mem::replace(&mut self.pattern_hits, BTreeMap::new())
+    }
+
+    /// Scan every committed value in the configured range for any of `candidates` occurring as
+    /// a substring, in a single forward pass, using Wu-Manber multi-pattern matching rather
+    /// than running one substring search per candidate per value. Matching never crosses value
+    /// boundaries: each value is checked independently. Returns the indices (in `candidates`'s
+    /// iteration order) of candidates that matched at least once; per-candidate hit counts are
+    /// accumulated into `take_pattern_hits`.
+    ///
+    /// Intended for reference/GC scanning: e.g. checking which of a known set of row keys are
+    /// still referenced by live values in a range, without one pass per candidate.
+    pub fn scan_values_for_patterns<P: Ord + AsRef<[u8]>>(
+        &mut self,
+        candidates: BTreeSet<P>,
+    ) -> Result<BTreeSet<usize>> {
+        let mut matched = BTreeSet::new();
+        if candidates.is_empty() {
+            return Ok(matched);
+        }
+        let matcher = WuManber::new(candidates.into_iter().collect());
+
+        while let Some((_, value)) = self.next()? {
+            for idx in matcher.find_matches(&value) {
+                matched.insert(idx);
+                *self.pattern_hits.entry(idx).or_insert(0) += 1;
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Get the next key-value pair from the multi-range scan configured via
+    /// `ScannerBuilder::ranges`, tagged with the index (into that range list) it came from.
+    /// Ranges are walked in the order given (descending): once the cursors step past the
+    /// current range's lower bound, they are re-anchored directly on the next range's upper
+    /// bound rather than continuing through the gap between them. If no ranges were configured,
+    /// this degrades to a plain `prev()` tagged with range index `0`.
+    pub fn prev_tagged(&mut self) -> Result<Option<(usize, Key, Value)>> {
+        if self.ranges.is_empty() {
+            return Ok(self.prev()?.map(|(key, value)| (0, key, value)));
+        }
+        loop {
+            if self.current_range >= self.ranges.len() {
+                return Ok(None);
+            }
+            let range_lower = self.ranges[self.current_range].0.clone();
+            match self.peek_next()? {
+                Some((ref key, _)) if range_lower.as_ref().map_or(true, |lb| key >= lb) => {
+                    let (key, value) = self.prev()?.expect("peek_next() guaranteed a result");
+                    return Ok(Some((self.current_range, key, value)));
+                }
+                Some(_) => {
+                    // The upcoming key has stepped past this range's lower bound, into the gap
+                    // before the next range (or straight into it). Don't consume it: advance to
+                    // the next range and re-anchor on its upper bound, then reconsider the same
+                    // key against the new range's bounds.
+                    self.current_range += 1;
+                    if self.current_range >= self.ranges.len() {
+                        return Ok(None);
+                    }
+                    let upper = self.ranges[self.current_range].1.clone();
+                    self.reanchor_to_upper_bound(upper.as_ref())?;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Re-anchor both cursors for a fresh backward scan starting at `upper` (exclusive), or at
+    /// the very last key in the underlying cursor bound if `upper` is `None`. Used by
+    /// `prev_tagged` to jump directly to the next configured range instead of stepping through
+    /// the gap before it.
+    fn reanchor_to_upper_bound(&mut self, upper: Option<&Key>) -> Result<()> {
+        match upper {
+            Some(key) => {
+                self.write_cursor.reverse_seek(key, &mut self.statistics.write)?;
+                self.lock_cursor.reverse_seek(key, &mut self.statistics.lock)?;
+            }
+            None => {
                 self.write_cursor.seek_to_last(&mut self.statistics.write);
                 self.lock_cursor.seek_to_last(&mut self.statistics.lock);
             }
+        }
+        self.direction = Direction::Backward;
+        self.is_started = true;
+        self.last_returned_key = None;
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Serialize the current scan position (which range of `ScannerBuilder::ranges` is active,
+    /// and the last key returned from it) into an opaque token that a later call to
+    /// `continue_from` can use to resume precisely here. Returns `None` before the first row has
+    /// been returned, since there is no position to serialize yet.
+    ///
+    /// Intended for bounded, paginated reverse scans: a caller can stop a `prev_tagged()` loop
+    /// after a row budget, hand the token to a client, and later rebuild an equivalent `Scanner`
+    /// (same snapshot, same `ranges`) and call `continue_from` on it instead of re-seeking from
+    /// the owning range's upper boundary.
+    pub fn continuation_token(&self) -> Option<Vec<u8>> {
+        let key = self.last_returned_key.as_ref()?;
+        let mut token = Vec::with_capacity(8 + key.encoded().len());
+        token.extend_from_slice(&encode_range_index(self.current_range));
+        token.extend_from_slice(key.encoded());
+        Some(token)
+    }
+
+    /// Resume a reverse scan from a token previously returned by `continuation_token`. The
+    /// cursors are re-anchored directly on the encoded key (exclusive, so it is not returned
+    /// again) rather than re-seeking from the owning range's upper boundary.
+    pub fn continue_from(&mut self, token: &[u8]) -> Result<()> {
+        if token.len() < 8 {
+            // Malformed/empty token: nothing to resume from, leave the scanner untouched.
+            return Ok(());
+        }
+        let current_range = decode_range_index(&token[..8]);
+        let key = Key::from_encoded_slice(&token[8..]);
+
+        self.write_cursor
+            .reverse_seek(&key, &mut self.statistics.write)?;
+        self.lock_cursor
+            .reverse_seek(&key, &mut self.statistics.lock)?;
+        self.current_range = current_range;
+        self.direction = Direction::Backward;
+        self.is_started = true;
+        self.last_returned_key = Some(key);
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Get the next key-value pair, in backward (descending) order. Drains `peek_next`'s
+    /// lookahead buffer first if it has already computed this result.
+    pub fn prev(&mut self) -> Result<Option<(Key, Value)>> {
+        if self.peeked.is_none() && self.limit_reached() {
+            return Ok(None);
+        }
+        self.ensure_direction(Direction::Backward)?;
+        let result = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.prev_impl()?,
+        };
+        if let Some((ref key, _)) = result {
+            self.last_returned_key = Some(key.clone());
+        }
+        Ok(result)
+    }
+
+    /// Return the key-value pair that the next `prev()` call would produce, without consuming
+    /// it: a subsequent `prev()` returns the same item. The expensive work (the
+    /// `prev`/`seek`/`seek_for_prev` cursor operations counted in `take_statistics`) happens at
+    /// most once per logical key regardless of how many times `peek_next()` is called between
+    /// advances, since the decoded result is cached in a one-slot lookahead buffer.
+    pub fn peek_next(&mut self) -> Result<Option<(Key, Value)>> {
+        if self.peeked.is_none() && self.limit_reached() {
+            return Ok(None);
+        }
+        self.ensure_direction(Direction::Backward)?;
+        if self.peeked.is_none() {
+            let result = self.prev_impl()?;
+            self.peeked = Some(result);
+        }
+        Ok(self.peeked.clone().unwrap())
+    }
+
+    /// Get the next key-value pair, in forward (ascending) order.
+    pub fn next(&mut self) -> Result<Option<(Key, Value)>> {
+        if self.limit_reached() {
+            return Ok(None);
+        }
+        self.ensure_direction(Direction::Forward)?;
+        let result = self.next_impl()?;
+        if let Some((ref key, _)) = result {
+            self.last_returned_key = Some(key.clone());
+        }
+        Ok(result)
+    }
+
+    /// Reposition the scanner to resume a forward scan from `user_key` (inclusive), as if it had
+    /// just returned the key before `user_key`. Useful for keyset pagination: a client can jump
+    /// to the first key of the next page and keep the same snapshot/cursors instead of building
+    /// a fresh scanner.
+    pub fn seek(&mut self, user_key: &Key) -> Result<()> {
+        self.write_cursor.seek(user_key, &mut self.statistics.write)?;
+        self.lock_cursor.seek(user_key, &mut self.statistics.lock)?;
+        self.direction = Direction::Forward;
+        self.is_started = true;
+        self.last_returned_key = None;
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Reposition the scanner to resume a backward scan ending at `user_key` (inclusive). The
+    /// write cursor is made to land on the earliest version of `user_key` so the `reverse_get`
+    /// contract ("cursor points at earliest version of the user key") still holds, and the lock
+    /// cursor is re-synced so the `has_write`/`has_lock` merge in `prev_impl` stays correct.
+    /// Useful for keyset pagination over a large reverse range: jump to the first key of the
+    /// next page (the key just below the previous page) and keep the same snapshot/cursors.
+    pub fn seek_for_prev(&mut self, user_key: &Key) -> Result<()> {
+        // `ts == 0` encodes to the largest possible suffix for `user_key` (see
+        // `move_write_cursor_to_next_user_key`), so seeking for the last key <= that anchor
+        // lands on the smallest-ts (i.e. earliest) version of `user_key` itself, if any.
+        let anchor = user_key.clone().append_ts(0);
+        self.write_cursor
+            .seek_for_prev(&anchor, &mut self.statistics.write)?;
+        self.lock_cursor
+            .seek_for_prev(user_key, &mut self.statistics.lock)?;
+        self.direction = Direction::Backward;
+        self.is_started = true;
+        self.last_returned_key = None;
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Reposition the scanner to the first key in range, ready for a forward scan.
+    pub fn seek_to_first(&mut self) -> Result<()> {
+        self.write_cursor.seek_to_first(&mut self.statistics.write);
+        self.lock_cursor.seek_to_first(&mut self.statistics.lock);
+        self.direction = Direction::Forward;
+        self.is_started = true;
+        self.last_returned_key = None;
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Reposition the scanner to the last key in range, ready for a backward scan.
+    pub fn seek_to_last(&mut self) -> Result<()> {
+        self.write_cursor.seek_to_last(&mut self.statistics.write);
+        self.lock_cursor.seek_to_last(&mut self.statistics.lock);
+        self.direction = Direction::Backward;
+        self.is_started = true;
+        self.last_returned_key = None;
+        self.peeked = None;
+        Ok(())
+    }
+
+    /// Make sure the cursors are anchored and walking in `want` direction. On the very first
+    /// call this performs the initial seek; on a direction flip it re-anchors both cursors on
+    /// `last_returned_key` instead of stepping the wrong way.
+    fn ensure_direction(&mut self, want: Direction) -> Result<()> {
+        if !self.is_started {
+            match want {
+                Direction::Backward => {
+                    if self.upper_bound.is_some() {
+                        // TODO: `seek_to_last` is better, however it has performance issues
+                        // currently.
+                        self.write_cursor.reverse_seek(
+                            self.upper_bound.as_ref().unwrap(),
+                            &mut self.statistics.write,
+                        )?;
+                        self.lock_cursor.reverse_seek(
+                            self.upper_bound.as_ref().unwrap(),
+                            &mut self.statistics.lock,
+                        )?;
+                    } else {
+                        self.write_cursor.seek_to_last(&mut self.statistics.write);
+                        self.lock_cursor.seek_to_last(&mut self.statistics.lock);
+                    }
+                }
+                Direction::Forward => {
+                    if self.lower_bound.is_some() {
+                        self.write_cursor.seek(
+                            self.lower_bound.as_ref().unwrap(),
+                            &mut self.statistics.write,
+                        )?;
+                        self.lock_cursor.seek(
+                            self.lower_bound.as_ref().unwrap(),
+                            &mut self.statistics.lock,
+                        )?;
+                    } else {
+                        self.write_cursor.seek_to_first(&mut self.statistics.write);
+                        self.lock_cursor.seek_to_first(&mut self.statistics.lock);
+                    }
+                }
+            }
+            self.direction = want;
             self.is_started = true;
+            return Ok(());
+        }
+
+        if self.direction != want {
+            // A lookahead buffered for the old direction is no longer valid once we re-anchor.
+            self.peeked = None;
+            if let Some(key) = self.last_returned_key.clone() {
+                match want {
+                    Direction::Backward => {
+                        // `reverse_seek` is exclusive of `key` itself, which is exactly what we
+                        // want: resume just before the key we already returned.
+                        self.write_cursor
+                            .reverse_seek(&key, &mut self.statistics.write)?;
+                        self.lock_cursor
+                            .reverse_seek(&key, &mut self.statistics.lock)?;
+                    }
+                    Direction::Forward => {
+                        // `seek` is inclusive, so to resume just after `key` we seek past all of
+                        // its versions: `ts == 0` encodes to the largest possible suffix for a
+                        // user key (see `move_write_cursor_to_next_user_key`).
+                        self.write_cursor.seek(
+                            &key.clone().append_ts(0),
+                            &mut self.statistics.write,
+                        )?;
+                        self.lock_cursor.seek(&key, &mut self.statistics.lock)?;
+                        if self.lock_cursor.valid()
+                            && self.lock_cursor.key(&mut self.statistics.lock)
+                                == key.encoded().as_slice()
+                        {
+                            self.lock_cursor.next(&mut self.statistics.lock);
+                        }
+                    }
+                }
+            }
+            self.direction = want;
+        }
+        Ok(())
+    }
+
+    /// Remove and return the greatest pending write that is `> bound` (or, if `bound` is
+    /// `None`, the greatest pending write of all). Used by `prev_impl` to interleave buffered
+    /// writes into the descending stream ahead of the snapshot candidate they sort after.
+    #[inline]
+    fn take_pending_write(&mut self, bound: Option<&Key>) -> Option<(Key, Option<Value>)> {
+        let candidate = {
+            let found = match bound {
+                Some(bound) => self
+                    .pending_writes
+                    .range((Excluded(bound.clone()), Unbounded))
+                    .next_back(),
+                None => self.pending_writes.iter().next_back(),
+            };
+            found.map(|(k, _)| k.clone())
+        }?;
+        self.pending_writes
+            .remove(&candidate)
+            .map(|value| (candidate, value))
+    }
+
+    /// Whether `limit` (if any) has already been reached, i.e. `prev()`/`next()` should return
+    /// `None` without touching the cursors at all.
+    #[inline]
+    fn limit_reached(&self) -> bool {
+        self.limit.map_or(false, |limit| self.emitted >= limit)
+    }
+
+    /// Resolve `offset` the first time the scanner is driven, in `direction`. A non-negative
+    /// offset just primes `offset_skip_remaining`, consumed by `prev_impl`/`next_impl` as they
+    /// emit results. A negative offset ("keep only the last `-offset` keys of the range") is
+    /// only resolved for a backward-driven scan with both range bounds specified: an initial
+    /// forward pass finds the start key of that window, and the scanner is repositioned there
+    /// with `seek_for_prev`; if fewer than `-offset` keys exist the whole range is kept instead.
+    /// In any other case (forward-driven scan, or a missing bound) a negative offset is ignored.
+    fn resolve_offset(&mut self, direction: Direction) -> Result<()> {
+        if self.offset_applied {
+            return Ok(());
+        }
+        self.offset_applied = true;
+        if self.offset >= 0 {
+            self.offset_skip_remaining = self.offset as u64;
+            return Ok(());
+        }
+        if direction != Direction::Backward || self.lower_bound.is_none()
+            || self.upper_bound.is_none()
+        {
+            return Ok(());
+        }
+
+        let keep = (-self.offset) as u64;
+        // Explicitly reposition to `lower_bound` rather than calling `ensure_direction(Forward)`:
+        // by this point `prev()` has already anchored the cursors at the top of the range and
+        // set `is_started`, so a direction-flip re-anchor would be a no-op (it only re-anchors
+        // on a flip when `last_returned_key` is `Some`, which it isn't yet here).
+        self.seek_to_first()?;
+        let mut last_kept_key = None;
+        for _ in 0..keep {
+            match self.next_impl_inner()? {
+                Some((key, _)) => last_kept_key = Some(key),
+                None => break,
+            }
+        }
+        match last_kept_key {
+            Some(key) => self.seek_for_prev(&key)?,
+            // Fewer than `keep` keys in the whole range: clamp to keeping all of them.
+            None => self.seek_to_last()?,
+        }
+        Ok(())
+    }
+
+    /// Get the next key-value pair, in backward order, applying `limit`/`offset`. Shared loop
+    /// body of `prev()`/`peek_next()`.
+    fn prev_impl(&mut self) -> Result<Option<(Key, Value)>> {
+        if self.limit_reached() {
+            return Ok(None);
+        }
+        self.resolve_offset(Direction::Backward)?;
+        loop {
+            match self.prev_impl_inner()? {
+                Some(item) => {
+                    if self.offset_skip_remaining > 0 {
+                        self.offset_skip_remaining -= 1;
+                        continue;
+                    }
+                    self.emitted += 1;
+                    return Ok(Some(item));
+                }
+                None => return Ok(None),
+            }
         }
+    }
 
-        // Similar to forward scanner, the general idea is to simultaneously step write
-        // cursor and lock cursor. Please refer to `ForwardScanner` for details.
+    /// Get the next key-value pair, in backward order, merging in the pending-write overlay.
+    /// Shared loop body of `prev_impl`.
+    fn prev_impl_inner(&mut self) -> Result<Option<(Key, Value)>> {
+        // Similar to forward scanning, the general idea is to simultaneously step write
+        // cursor and lock cursor.
 
         loop {
-            let (current_user_key, has_write, has_lock) = {
+            let current_user_key = {
                 let w_key = if self.write_cursor.valid() {
                     Some(self.write_cursor.key(&mut self.statistics.write))
                 } else {
@@ -205,33 +1002,49 @@ impl<S: Snapshot> BackwardScanner<S> {
                     None
                 };
 
-                // `res` is `(current_user_key_slice, has_write, has_lock)`
-                let res = match (w_key, l_key) {
-                    (None, None) => return Ok(None),
-                    (None, Some(lk)) => (lk, false, true),
-                    (Some(wk), None) => (Key::truncate_ts_for(wk)?, true, false),
+                match (w_key, l_key) {
+                    (None, None) => None,
+                    (None, Some(lk)) => Some(Key::from_encoded_slice(lk)),
+                    (Some(wk), None) => {
+                        // Use `from_encoded_slice` to reserve space for ts, so later we can
+                        // append ts to the key or its clones without reallocation.
+                        Some(Key::from_encoded_slice(Key::truncate_ts_for(wk)?))
+                    }
                     (Some(wk), Some(lk)) => {
                         let write_user_key = Key::truncate_ts_for(wk)?;
-                        match write_user_key.cmp(lk) {
-                            Ordering::Less => {
-                                // We are scanning from largest user key to smallest user key, so this
-                                // indicate that we meet a lock first, thus its corresponding write
-                                // does not exist.
-                                (lk, false, true)
-                            }
-                            Ordering::Greater => {
-                                // We meet write first, so the lock of the write key does not exist.
-                                (write_user_key, true, false)
-                            }
-                            Ordering::Equal => (write_user_key, true, true),
-                        }
+                        let raw = match write_user_key.cmp(lk) {
+                            Ordering::Less => lk,
+                            Ordering::Greater | Ordering::Equal => write_user_key,
+                        };
+                        Some(Key::from_encoded_slice(raw))
                     }
-                };
+                }
+            };
 
-                // Use `from_encoded_slice` to reserve space for ts, so later we can append ts to
-                // the key or its clones without reallocation.
-                (Key::from_encoded_slice(res.0), res.1, res.2)
+            // A buffered write that sorts after the snapshot's current candidate (or, once the
+            // snapshot cursors are exhausted, any remaining buffered write) must be emitted
+            // before we touch the cursors, so the merged stream stays in descending order.
+            if let Some((pending_key, pending_value)) =
+                self.take_pending_write(current_user_key.as_ref())
+            {
+                match pending_value {
+                    Some(value) => return Ok(Some((pending_key, value))),
+                    None => continue, // pending tombstone, keep looking
+                }
+            }
+
+            let current_user_key = match current_user_key {
+                Some(key) => key,
+                None => return Ok(None),
             };
+            let has_write = self.write_cursor.valid()
+                && Key::is_user_key_eq(
+                    self.write_cursor.key(&mut self.statistics.write),
+                    current_user_key.encoded().as_slice(),
+                );
+            let has_lock = self.lock_cursor.valid()
+                && self.lock_cursor.key(&mut self.statistics.lock)
+                    == current_user_key.encoded().as_slice();
 
             let mut result = Ok(None);
             let mut get_ts = self.ts;
@@ -263,36 +1076,140 @@ impl<S: Snapshot> BackwardScanner<S> {
                 }
             }
 
+            // A pending write overlay on this exact key overrides the snapshot result: `Some`
+            // replaces the value, `None` suppresses it. Either way it's consumed here so a
+            // later call does not see it again.
+            if let Some(pending_value) = self.pending_writes.remove(&current_user_key) {
+                match pending_value {
+                    Some(value) => return Ok(Some((current_user_key, value))),
+                    None => continue,
+                }
+            }
+
             if let Some(v) = result? {
                 return Ok(Some((current_user_key, v)));
             }
         }
     }
 
-    /// Attempt to get the value of a key specified by `user_key` and `self.ts` in reverse order.
-    /// This function requires that the write cursor is currently pointing to the earliest version
-    /// of `user_key`.
-    #[inline]
-    fn reverse_get(
-        &mut self,
-        user_key: &Key,
-        ts: u64,
-        met_prev_user_key: &mut bool,
-    ) -> Result<Option<Value>> {
-        assert!(self.write_cursor.valid());
-
-        // At first, we try to use several `prev()` to get the desired version.
-
-        // We need to save last desired version, because when we may move to an unwanted version
-        // at any time.
-        let mut last_version = None;
-        let mut last_checked_commit_ts = 0;
+    /// Get the next key-value pair, in forward order, applying `limit`/`offset`. Shared loop
+    /// body of `next()`.
+    fn next_impl(&mut self) -> Result<Option<(Key, Value)>> {
+        if self.limit_reached() {
+            return Ok(None);
+        }
+        self.resolve_offset(Direction::Forward)?;
+        loop {
+            match self.next_impl_inner()? {
+                Some(item) => {
+                    if self.offset_skip_remaining > 0 {
+                        self.offset_skip_remaining -= 1;
+                        continue;
+                    }
+                    self.emitted += 1;
+                    return Ok(Some(item));
+                }
+                None => return Ok(None),
+            }
+        }
+    }
 
-        for i in 0..REVERSE_SEEK_BOUND {
-            if i > 0 {
-                // We are already pointing at the smallest version, so we don't need to prev()
+    /// Get the next key-value pair, in forward order. Shared loop body of `next_impl`.
+    fn next_impl_inner(&mut self) -> Result<Option<(Key, Value)>> {
+        loop {
+            let (current_user_key, has_write, has_lock) = {
+                let w_key = if self.write_cursor.valid() {
+                    Some(self.write_cursor.key(&mut self.statistics.write))
+                } else {
+                    None
+                };
+                let l_key = if self.lock_cursor.valid() {
+                    Some(self.lock_cursor.key(&mut self.statistics.lock))
+                } else {
+                    None
+                };
+
+                let res = match (w_key, l_key) {
+                    (None, None) => return Ok(None),
+                    (None, Some(lk)) => (lk, false, true),
+                    (Some(wk), None) => (Key::truncate_ts_for(wk)?, true, false),
+                    (Some(wk), Some(lk)) => {
+                        let write_user_key = Key::truncate_ts_for(wk)?;
+                        match write_user_key.cmp(lk) {
+                            Ordering::Less => {
+                                // We are scanning from smallest user key to largest, so this
+                                // indicates we meet a write first, its corresponding lock does
+                                // not exist.
+                                (write_user_key, true, false)
+                            }
+                            Ordering::Greater => (lk, false, true),
+                            Ordering::Equal => (write_user_key, true, true),
+                        }
+                    }
+                };
+
+                (Key::from_encoded_slice(res.0), res.1, res.2)
+            };
+
+            let mut result = Ok(None);
+            let mut get_ts = self.ts;
+            let mut met_next_user_key = false;
+
+            if has_lock {
+                match self.isolation_level {
+                    IsolationLevel::SI => match super::util::load_and_check_lock_from_cursor(
+                        &mut self.lock_cursor,
+                        &current_user_key,
+                        self.ts,
+                        &mut self.statistics,
+                    )? {
+                        CheckLockResult::NotLocked => {}
+                        CheckLockResult::Locked(e) => result = Err(e),
+                        CheckLockResult::Ignored(ts) => get_ts = ts,
+                    },
+                    IsolationLevel::RC => {}
+                }
+                self.lock_cursor.next(&mut self.statistics.lock);
+            }
+            if has_write {
+                if result.is_ok() {
+                    result = self.forward_get(&current_user_key, get_ts, &mut met_next_user_key);
+                }
+                if !met_next_user_key {
+                    self.move_write_cursor_to_next_user_key(&current_user_key)?;
+                }
+            }
+
+            if let Some(v) = result? {
+                return Ok(Some((current_user_key, v)));
+            }
+        }
+    }
+
+    /// Attempt to get the value of a key specified by `user_key` and `self.ts` in reverse order.
+    /// This function requires that the write cursor is currently pointing to the earliest version
+    /// of `user_key`.
+    #[inline]
+    fn reverse_get(
+        &mut self,
+        user_key: &Key,
+        ts: u64,
+        met_prev_user_key: &mut bool,
+    ) -> Result<Option<Value>> {
+        assert!(self.write_cursor.valid());
+
+        // At first, we try to use several `prev()` to get the desired version.
+
+        // We need to save last desired version, because when we may move to an unwanted version
+        // at any time.
+        let mut last_version = None;
+        let mut last_checked_commit_ts = 0;
+
+        for i in 0..self.reverse_seek_bound {
+            if i > 0 {
+                // We are already pointing at the smallest version, so we don't need to prev()
                 // for the first iteration. So we will totally call `prev()` function
-                // `REVERSE_SEEK_BOUND - 1` times.
+                // `reverse_seek_bound - 1` times.
                 self.write_cursor.prev(&mut self.statistics.write);
                 if !self.write_cursor.valid() {
                     // Key space ended. We use `last_version` as the return.
@@ -323,7 +1240,7 @@ impl<S: Snapshot> BackwardScanner<S> {
 
             match write.write_type {
                 WriteType::Put | WriteType::Delete => last_version = Some(write),
-                WriteType::Lock | WriteType::Rollback => {}
+                WriteType::Lock | WriteType::Rollback => self.skipped_versions += 1,
             }
         }
 
@@ -335,7 +1252,11 @@ impl<S: Snapshot> BackwardScanner<S> {
         assert!(ts > last_checked_commit_ts);
 
         // After several `prev()`, we still not get the latest version for the specified ts,
-        // use seek to locate the latest version.
+        // use seek to locate the latest version. This key has more versions than
+        // `reverse_seek_bound`, which is exactly the "pathologically many versions" signal GC
+        // should prioritize, so remember it.
+        self.record_hot_version_key(user_key);
+
         // `user_key` must have reserved space here, so its clone has reserved space too. So no
         // reallocation happends in `append_ts`.
         let seek_key = user_key.clone().append_ts(ts);
@@ -373,6 +1294,7 @@ impl<S: Snapshot> BackwardScanner<S> {
                 WriteType::Delete => return Ok(None),
                 WriteType::Lock | WriteType::Rollback => {
                     // Continue iterate next `write`.
+                    self.skipped_versions += 1;
                     self.write_cursor.next(&mut self.statistics.write);
                     assert!(self.write_cursor.valid());
                 }
@@ -380,6 +1302,93 @@ impl<S: Snapshot> BackwardScanner<S> {
         }
     }
 
+    /// Attempt to get the value of a key specified by `user_key` and `self.ts` in forward order.
+    /// This function requires that the write cursor is currently pointing to the latest version
+    /// of `user_key`. Unlike `reverse_get`, walking forward can never overshoot the desired
+    /// version: the first version encountered with `commit_ts <= ts` (once locks/rollbacks are
+    /// skipped) is exactly the one we want, so there is no need to remember a "last" candidate.
+    #[inline]
+    fn forward_get(
+        &mut self,
+        user_key: &Key,
+        ts: u64,
+        met_next_user_key: &mut bool,
+    ) -> Result<Option<Value>> {
+        assert!(self.write_cursor.valid());
+
+        // At first, we try to use several `next()` to skip versions newer than `ts` as well as
+        // rollbacks/locks, and land directly on the desired version.
+        for i in 0..self.seek_bound {
+            if i > 0 {
+                self.write_cursor.next(&mut self.statistics.write);
+            }
+            if !self.write_cursor.valid() {
+                *met_next_user_key = true;
+                return Ok(None);
+            }
+
+            let current_ts = {
+                let current_key = self.write_cursor.key(&mut self.statistics.write);
+                if !Key::is_user_key_eq(current_key, user_key.encoded().as_slice()) {
+                    // Meet another key. There is no desired version for `user_key`.
+                    *met_next_user_key = true;
+                    return Ok(None);
+                }
+                Key::decode_ts_from(current_key)?
+            };
+            if current_ts > ts {
+                // Still newer than what we want, keep stepping forward.
+                continue;
+            }
+
+            let write = Write::parse(self.write_cursor.value(&mut self.statistics.write))?;
+            self.statistics.write.processed += 1;
+
+            match write.write_type {
+                WriteType::Put => return Ok(Some(self.load_data_by_write(write, user_key)?)),
+                WriteType::Delete => return Ok(None),
+                WriteType::Lock | WriteType::Rollback => {
+                    // Continue skipping obsolete versions.
+                    self.skipped_versions += 1;
+                }
+            }
+        }
+
+        // After `seek_bound` tries we still have not resolved the version (typically because
+        // of a long run of locks/rollbacks), seek directly to the desired ts. Same hot-version
+        // signal as the backward path.
+        self.record_hot_version_key(user_key);
+        let seek_key = user_key.clone().append_ts(ts);
+        self.write_cursor
+            .internal_seek(&seek_key, &mut self.statistics.write)?;
+
+        loop {
+            if !self.write_cursor.valid() {
+                *met_next_user_key = true;
+                return Ok(None);
+            }
+            {
+                let current_key = self.write_cursor.key(&mut self.statistics.write);
+                if !Key::is_user_key_eq(current_key, user_key.encoded().as_slice()) {
+                    *met_next_user_key = true;
+                    return Ok(None);
+                }
+            }
+
+            let write = Write::parse(self.write_cursor.value(&mut self.statistics.write))?;
+            self.statistics.write.processed += 1;
+
+            match write.write_type {
+                WriteType::Put => return Ok(Some(self.load_data_by_write(write, user_key)?)),
+                WriteType::Delete => return Ok(None),
+                WriteType::Lock | WriteType::Rollback => {
+                    self.skipped_versions += 1;
+                    self.write_cursor.next(&mut self.statistics.write);
+                }
+            }
+        }
+    }
+
     /// Handle last version. Last version may be PUT or DELETE. If it is a PUT, value should be
     /// load.
     #[inline]
@@ -398,8 +1407,9 @@ impl<S: Snapshot> BackwardScanner<S> {
         }
     }
 
-    /// Load the value by the given `some_write`. If value is carried in `some_write`, it will be
-    /// returned directly. Otherwise there will be a default CF look up.
+    /// Load the value by the given `some_write`, walking the default cursor backward. If value
+    /// is carried in `some_write`, it will be returned directly. Otherwise there will be a
+    /// default CF look up.
     ///
     /// The implementation is similar to `PointGetter::load_data_by_write`.
     #[inline]
@@ -426,6 +1436,28 @@ impl<S: Snapshot> BackwardScanner<S> {
         }
     }
 
+    /// Load the value by the given `some_write`, walking the default cursor forward. Mirror of
+    /// `reverse_load_data_by_write` for the forward scan direction.
+    #[inline]
+    fn load_data_by_write(&mut self, write: Write, user_key: &Key) -> Result<Value> {
+        if self.omit_value {
+            return Ok(vec![]);
+        }
+        match write.short_value {
+            Some(value) => Ok(value),
+            None => {
+                self.ensure_default_cursor()?;
+                let value = super::util::near_load_data_by_write(
+                    &mut self.default_cursor.as_mut().unwrap(),
+                    user_key,
+                    write,
+                    &mut self.statistics,
+                )?;
+                Ok(value)
+            }
+        }
+    }
+
     /// After `self.reverse_get()`, our write cursor may be pointing to current user key (if we
     /// found a desired version), or previous user key (if there is no desired version), or
     /// out of bound.
@@ -435,7 +1467,7 @@ impl<S: Snapshot> BackwardScanner<S> {
     /// key, we `seek_for_prev()`.
     #[inline]
     fn move_write_cursor_to_prev_user_key(&mut self, current_user_key: &Key) -> Result<()> {
-        for i in 0..SEEK_BOUND {
+        for i in 0..self.seek_bound {
             if i > 0 {
                 self.write_cursor.prev(&mut self.statistics.write);
             }
@@ -453,13 +1485,47 @@ impl<S: Snapshot> BackwardScanner<S> {
         }
 
         // We have not found another user key for now, so we directly `seek_for_prev()`.
-        // After that, we must pointing to another key, or out of bound.
+        // After that, we must pointing to another key, or out of bound. `current_user_key` has
+        // more rollback/lock/obsolete versions than `seek_bound`, so it's another hot-version
+        // candidate for GC.
+        self.record_hot_version_key(current_user_key);
         self.write_cursor
             .internal_seek_for_prev(current_user_key, &mut self.statistics.write)?;
 
         Ok(())
     }
 
+    /// Mirror of `move_write_cursor_to_prev_user_key` for forward scanning: after
+    /// `self.forward_get()`, step the write cursor until we meet the next user key, falling back
+    /// to a direct `seek()` once `seek_bound - 1` plain `next()`s have not found one.
+    #[inline]
+    fn move_write_cursor_to_next_user_key(&mut self, current_user_key: &Key) -> Result<()> {
+        for i in 0..self.seek_bound {
+            if i > 0 {
+                self.write_cursor.next(&mut self.statistics.write);
+            }
+            if !self.write_cursor.valid() {
+                return Ok(());
+            }
+            {
+                let current_key = self.write_cursor.key(&mut self.statistics.write);
+                if !Key::is_user_key_eq(current_key, current_user_key.encoded().as_slice()) {
+                    return Ok(());
+                }
+            }
+        }
+
+        self.record_hot_version_key(current_user_key);
+
+        // `ts == 0` encodes to the largest possible suffix for `current_user_key`, i.e. a key
+        // that sorts past all of its versions, so seeking to it lands on the next user key.
+        let seek_key = current_user_key.clone().append_ts(0);
+        self.write_cursor
+            .internal_seek(&seek_key, &mut self.statistics.write)?;
+
+        Ok(())
+    }
+
     /// Create the default cursor if it doesn't exist.
     fn ensure_default_cursor(&mut self) -> Result<()> {
         if self.default_cursor.is_some() {
@@ -468,13 +1534,60 @@ impl<S: Snapshot> BackwardScanner<S> {
         let cursor = CursorBuilder::new(&self.snapshot, CF_DEFAULT)
             .range(self.lower_bound.take(), self.upper_bound.take())
             .fill_cache(self.fill_cache)
-            .scan_mode(ScanMode::Backward)
+            .scan_mode(ScanMode::Mixed)
             .build()?;
         self.default_cursor = Some(cursor);
         Ok(())
     }
 }
 
+/// Adapts a `Scanner` into a standard `Iterator` that walks backward (descending), driving it
+/// via `Scanner::prev()`, so it can be composed with `take`, `skip`, `filter`, `step_by` and
+/// friends instead of a hand-rolled loop. Statistics accounting is unaffected, since every
+/// `Iterator::next()` call still funnels through `prev()`.
+///
+/// Fused: once `prev()` returns `None` or `Err`, every subsequent call also returns `None`,
+/// matching the "cursor remains invalid, so nothing should happen" invariant the scanner
+/// otherwise relies on.
+pub struct BackwardIter<S: Snapshot> {
+    scanner: Scanner<S>,
+    done: bool,
+}
+
+impl<S: Snapshot> Iterator for BackwardIter<S> {
+    type Item = Result<(Key, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.scanner.prev() {
+            Ok(Some(kv)) => Some(Ok(kv)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<S: Snapshot> IntoIterator for Scanner<S> {
+    type Item = Result<(Key, Value)>;
+    type IntoIter = BackwardIter<S>;
+
+    /// Drive this scanner backward (descending) as a standard `Iterator`. See `BackwardIter`.
+    fn into_iter(self) -> Self::IntoIter {
+        BackwardIter {
+            scanner: self,
+            done: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -557,7 +1670,7 @@ mod tests {
         // 4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND)
+        let mut scanner = ScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND)
             .range(None, Some(Key::from_raw(&[11 as u8])))
             .build()
             .unwrap();
@@ -573,7 +1686,7 @@ mod tests {
         // 4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
         //                                             ^
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((
                 Key::from_raw(&[10 as u8]),
                 vec![(REVERSE_SEEK_BOUND / 2 - 1) as u8]
@@ -604,7 +1717,7 @@ mod tests {
         // 4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
         //                                   ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[9 as u8]), vec![REVERSE_SEEK_BOUND as u8]))
         );
         let statistics = scanner.take_statistics();
@@ -638,7 +1751,7 @@ mod tests {
         // 4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
         //                         ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((
                 Key::from_raw(&[8 as u8]),
                 vec![(REVERSE_SEEK_BOUND / 2 - 1) as u8]
@@ -676,7 +1789,7 @@ mod tests {
         // 4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
         //             ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[6 as u8]), vec![0 as u8]))
         );
         let statistics = scanner.take_statistics();
@@ -711,7 +1824,7 @@ mod tests {
         //   4 4 5 5 5 5 5 6 7 7 7 7 7 8 8 8 8 8 9 9 9 9 9 10 10
         // ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[4 as u8]), vec![REVERSE_SEEK_BOUND as u8]))
         );
         let statistics = scanner.take_statistics();
@@ -721,7 +1834,7 @@ mod tests {
         assert_eq!(statistics.write.seek_for_prev, 0);
 
         // Scan end.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.prev, 0);
         assert_eq!(statistics.write.seek, 0);
@@ -729,7 +1842,7 @@ mod tests {
         assert_eq!(statistics.write.seek_for_prev, 0);
     }
 
-    /// Check whether everything works as usual when `BackwardScanner::reverse_get()` goes
+    /// Check whether everything works as usual when `Scanner::reverse_get()` goes
     /// out of bound.
     ///
     /// Case 1. prev out of bound, next_version is None.
@@ -752,7 +1865,7 @@ mod tests {
         );
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND * 2)
+        let mut scanner = ScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND * 2)
             .range(None, None)
             .build()
             .unwrap();
@@ -769,7 +1882,7 @@ mod tests {
         //   b_1 b_0 c_8
         //       ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"c"), b"value".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -781,7 +1894,7 @@ mod tests {
         // Use N/2 prev and reach out of bound:
         //   b_1 b_0 c_8
         // ^cursor
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -789,7 +1902,7 @@ mod tests {
         assert_eq!(statistics.write.prev, (REVERSE_SEEK_BOUND / 2) as usize);
 
         // Cursor remains invalid, so nothing should happen.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -797,7 +1910,7 @@ mod tests {
         assert_eq!(statistics.write.prev, 0);
     }
 
-    /// Check whether everything works as usual when `BackwardScanner::reverse_get()` goes
+    /// Check whether everything works as usual when `Scanner::reverse_get()` goes
     /// out of bound.
     ///
     /// Case 2. prev out of bound, next_version is Some.
@@ -822,7 +1935,7 @@ mod tests {
         );
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND * 2)
+        let mut scanner = ScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND * 2)
             .range(None, None)
             .build()
             .unwrap();
@@ -839,7 +1952,7 @@ mod tests {
         //   b_2 b_1 b_0 c_8
         //           ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"c"), b"value_c".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -852,7 +1965,7 @@ mod tests {
         //   b_2 b_1 b_0 c_8
         // ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"b"), b"value_b".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -862,7 +1975,7 @@ mod tests {
         assert_eq!(statistics.write.prev, (REVERSE_SEEK_BOUND / 2 + 1) as usize);
 
         // Cursor remains invalid, so nothing should happen.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -871,7 +1984,7 @@ mod tests {
     }
 
     /// Check whether everything works as usual when
-    /// `BackwardScanner::move_write_cursor_to_prev_user_key()` goes out of bound.
+    /// `Scanner::move_write_cursor_to_prev_user_key()` goes out of bound.
     ///
     /// Case 1. prev() out of bound
     #[test]
@@ -889,7 +2002,7 @@ mod tests {
         }
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, 1)
+        let mut scanner = ScannerBuilder::new(snapshot, 1)
             .range(None, None)
             .build()
             .unwrap();
@@ -906,7 +2019,7 @@ mod tests {
         //   b_2 b_1 c_1
         //       ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"c"), b"value".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -923,7 +2036,7 @@ mod tests {
         //   b_2 b_1 c_1
         // ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"b"), vec![1u8].to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -933,7 +2046,7 @@ mod tests {
         assert_eq!(statistics.write.prev, (SEEK_BOUND / 2) as usize);
 
         // Next we should get nothing.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -942,7 +2055,7 @@ mod tests {
     }
 
     /// Check whether everything works as usual when
-    /// `BackwardScanner::move_write_cursor_to_prev_user_key()` goes out of bound.
+    /// `Scanner::move_write_cursor_to_prev_user_key()` goes out of bound.
     ///
     /// Case 2. seek_for_prev() out of bound
     #[test]
@@ -960,7 +2073,7 @@ mod tests {
         }
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, 1)
+        let mut scanner = ScannerBuilder::new(snapshot, 1)
             .range(None, None)
             .build()
             .unwrap();
@@ -977,7 +2090,7 @@ mod tests {
         //   b_5 b_4 b_3 b_2 b_1 c_1
         //                   ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"c"), b"value".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -1000,7 +2113,7 @@ mod tests {
         //   b_5 b_4 b_3 b_2 b_1 c_1
         // ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"b"), vec![1u8])),
         );
         let statistics = scanner.take_statistics();
@@ -1010,7 +2123,7 @@ mod tests {
         assert_eq!(statistics.write.prev, SEEK_BOUND as usize);
 
         // Next we should get nothing.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -1019,7 +2132,7 @@ mod tests {
     }
 
     /// Check whether everything works as usual when
-    /// `BackwardScanner::move_write_cursor_to_prev_user_key()` goes out of bound.
+    /// `Scanner::move_write_cursor_to_prev_user_key()` goes out of bound.
     ///
     /// Case 3. a more complicated case
     #[test]
@@ -1039,7 +2152,7 @@ mod tests {
         }
 
         let snapshot = engine.snapshot(&Context::new()).unwrap();
-        let mut scanner = BackwardScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND + 1)
+        let mut scanner = ScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND + 1)
             .range(None, None)
             .build()
             .unwrap();
@@ -1056,7 +2169,7 @@ mod tests {
         //   b_11 b_10 b_9 b_8 b_7 b_6 b_5 b_4 b_3 b_2 b_1 c_1
         //                                             ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"c"), b"value".to_vec())),
         );
         let statistics = scanner.take_statistics();
@@ -1082,7 +2195,7 @@ mod tests {
         //   b_11 b_10 b_9 b_8 b_7 b_6 b_5 b_4 b_3 b_2 b_1 c_1
         // ^cursor
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(b"b"), vec![(REVERSE_SEEK_BOUND + 1) as u8])),
         );
         let statistics = scanner.take_statistics();
@@ -1095,7 +2208,7 @@ mod tests {
         );
 
         // Next we should get nothing.
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.write.seek, 0);
         assert_eq!(statistics.write.seek_for_prev, 0);
@@ -1126,80 +2239,80 @@ mod tests {
         let snapshot = engine.snapshot(&Context::new()).unwrap();
 
         // Test both bound specified.
-        let mut scanner = BackwardScannerBuilder::new(snapshot.clone(), 10)
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
             .range(Some(Key::from_raw(&[3u8])), Some(Key::from_raw(&[5u8])))
             .build()
             .unwrap();
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[4u8]), vec![4u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[3u8]), vec![3u8]))
         );
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
 
         // Test left bound not specified.
-        let mut scanner = BackwardScannerBuilder::new(snapshot.clone(), 10)
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
             .range(None, Some(Key::from_raw(&[3u8])))
             .build()
             .unwrap();
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[2u8]), vec![2u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[1u8]), vec![1u8]))
         );
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
 
         // Test right bound not specified.
-        let mut scanner = BackwardScannerBuilder::new(snapshot.clone(), 10)
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
             .range(Some(Key::from_raw(&[5u8])), None)
             .build()
             .unwrap();
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[6u8]), vec![6u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[5u8]), vec![5u8]))
         );
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
 
         // Test both bound not specified.
-        let mut scanner = BackwardScannerBuilder::new(snapshot.clone(), 10)
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
             .range(None, None)
             .build()
             .unwrap();
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[6u8]), vec![6u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[5u8]), vec![5u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[4u8]), vec![4u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[3u8]), vec![3u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[2u8]), vec![2u8]))
         );
         assert_eq!(
-            scanner.read_next().unwrap(),
+            scanner.prev().unwrap(),
             Some((Key::from_raw(&[1u8]), vec![1u8]))
         );
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
     }
 
     #[test]
@@ -1234,14 +2347,615 @@ mod tests {
 
         // Call reverse scan
         let ts = 2;
-        let mut scanner = BackwardScannerBuilder::new(snapshot, ts)
+        let mut scanner = ScannerBuilder::new(snapshot, ts)
             .range(None, Some(k))
             .build()
             .unwrap();
-        assert_eq!(scanner.read_next().unwrap(), None);
+        assert_eq!(scanner.prev().unwrap(), None);
         let statistics = scanner.take_statistics();
         assert_eq!(statistics.lock.prev, 256);
         assert_eq!(statistics.write.prev, 1);
     }
 
+    /// A key with more versions than `REVERSE_SEEK_BOUND` forces `reverse_get` to fall back to
+    /// a `seek`, which should be reported as a hot-version sample.
+    #[test]
+    fn test_sampled_hot_keys() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        let k = b"k";
+        for ts in 0..REVERSE_SEEK_BOUND + 1 {
+            must_prewrite_put(&engine, k, &[ts as u8], k, ts);
+            must_commit(&engine, k, ts, ts);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, REVERSE_SEEK_BOUND)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(k), vec![REVERSE_SEEK_BOUND as u8]))
+        );
+        assert_eq!(scanner.take_sampled_hot_keys(), vec![k.to_vec()]);
+        // Samples are reset after being taken.
+        assert!(scanner.take_sampled_hot_keys().is_empty());
+    }
+
+    /// `Rollback`s skipped while walking a single key's version chain to find the latest
+    /// version at or below `ts` are counted by `take_skipped_versions`, independently of
+    /// whether the chain is long enough to trip the `reverse_seek_bound`/`seek_bound` fallback.
+    #[test]
+    fn test_skipped_versions() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        let k = b"k";
+        must_prewrite_put(&engine, k, &[1], k, 1);
+        must_commit(&engine, k, 1, 1);
+        for ts in 2..4 {
+            must_prewrite_put(&engine, k, &[0xff], k, ts);
+            must_rollback(&engine, k, ts);
+        }
+        must_prewrite_put(&engine, k, &[4], k, 4);
+        must_commit(&engine, k, 4, 4);
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 4)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        assert_eq!(scanner.prev().unwrap(), Some((Key::from_raw(k), vec![4])));
+        assert_eq!(scanner.take_skipped_versions(), 2);
+        // Counter is reset after being taken.
+        assert_eq!(scanner.prev().unwrap(), None);
+        assert_eq!(scanner.take_skipped_versions(), 0);
+    }
+
+    /// `ScannerBuilder::reverse_seek_bound` lowers the prev-vs-seek crossover point, so a key
+    /// with fewer versions than the default `REVERSE_SEEK_BOUND` can still trip the fallback
+    /// `seek_for_prev` and get reported as a hot-version sample.
+    #[test]
+    fn test_tunable_reverse_seek_bound() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        let k = b"k";
+        for ts in 0..4 {
+            must_prewrite_put(&engine, k, &[ts as u8], k, ts);
+            must_commit(&engine, k, ts, ts);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 3)
+            .range(None, None)
+            .reverse_seek_bound(2)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(k), vec![3 as u8]))
+        );
+        let statistics = scanner.take_statistics();
+        // Only `reverse_seek_bound - 1` prev()s are tried before falling back to a seek.
+        assert_eq!(statistics.write.prev, 1);
+        assert_eq!(scanner.take_sampled_hot_keys(), vec![k.to_vec()]);
+    }
+
+    /// A scanner built from a fresh snapshot can walk forward with `next()` just like it can
+    /// walk backward with `prev()`.
+    #[test]
+    fn test_forward() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        for i in 1..7 {
+            assert_eq!(
+                scanner.next().unwrap(),
+                Some((Key::from_raw(&[i]), vec![i]))
+            );
+        }
+        assert_eq!(scanner.next().unwrap(), None);
+    }
+
+    /// Switching direction mid-scan re-anchors on the last returned key instead of replaying
+    /// keys already seen.
+    #[test]
+    fn test_direction_switch() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[2]), vec![2]))
+        );
+        // Flip to backward: should return key [1] again, not replay [2].
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(scanner.prev().unwrap(), None);
+        // Flip back to forward: should resume after [1].
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[2]), vec![2]))
+        );
+    }
+
+    /// `seek_for_prev` lets a client resume a reverse keyset-paginated scan from the last key
+    /// of the previous page without rebuilding the scanner.
+    #[test]
+    fn test_seek_for_prev_pagination() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        // First page: the two largest keys.
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[6]), vec![6]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[5]), vec![5]))
+        );
+
+        // Jump straight to the next page, as if a fresh scanner had been built with
+        // `range(None, Some(Key::from_raw(&[5])))`.
+        scanner.seek_for_prev(&Key::from_raw(&[4])).unwrap();
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[4]), vec![4]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+    }
+
+    /// `seek` lets a client resume a forward keyset-paginated scan from the first key of the
+    /// next page without rebuilding the scanner.
+    #[test]
+    fn test_seek_pagination() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[2]), vec![2]))
+        );
+
+        scanner.seek(&Key::from_raw(&[4])).unwrap();
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[4]), vec![4]))
+        );
+        assert_eq!(
+            scanner.next().unwrap(),
+            Some((Key::from_raw(&[5]), vec![5]))
+        );
+    }
+
+    /// A `Scanner` can be driven as a standard `Iterator`, composing with adapters like `take`.
+    #[test]
+    fn test_into_iter() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        let results: Vec<_> = scanner
+            .into_iter()
+            .take(3)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (Key::from_raw(&[6]), vec![6]),
+                (Key::from_raw(&[5]), vec![5]),
+                (Key::from_raw(&[4]), vec![4]),
+            ]
+        );
+    }
+
+    /// `peek_next` can be called repeatedly without consuming the item or repeating cursor work,
+    /// and a subsequent `prev()` returns the same item.
+    #[test]
+    fn test_peek_next() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..4 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.peek_next().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+        scanner.take_statistics();
+
+        // Peeking again must not repeat the cursor work.
+        assert_eq!(
+            scanner.peek_next().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+        let stats_after_second_peek = scanner.take_statistics();
+        assert_eq!(stats_after_second_peek.write.seek_for_prev, 0);
+        assert_eq!(stats_after_second_peek.write.prev, 0);
+
+        // `prev()` returns the same peeked item, draining the buffer.
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[2]), vec![2]))
+        );
+    }
+
+    /// `ScannerBuilder::pending_writes` overlays a transaction's own not-yet-committed writes
+    /// onto the snapshot: a buffered `Some` overrides an existing snapshot value, a buffered
+    /// `None` suppresses one, and a buffered key with no snapshot counterpart is interleaved in
+    /// descending order between its neighbours.
+    #[test]
+    fn test_pending_writes() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        // Committed keys [1], [2], [4], [5].
+        for i in &[1u8, 2, 4, 5] {
+            must_prewrite_put(&engine, &[*i], &[*i], &[*i], 1);
+            must_commit(&engine, &[*i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+
+        let mut pending_writes = BTreeMap::new();
+        // Overrides the committed value of [2].
+        pending_writes.insert(Key::from_raw(&[2]), Some(vec![0xff]));
+        // Not present in the snapshot at all; must be interleaved between [4] and [2].
+        pending_writes.insert(Key::from_raw(&[3]), Some(vec![3]));
+        // Suppresses the committed value of [5].
+        pending_writes.insert(Key::from_raw(&[5]), None);
+        // Out of the scan range, must be dropped and never observed.
+        pending_writes.insert(Key::from_raw(&[9]), Some(vec![9]));
+
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, Some(Key::from_raw(&[6])))
+            .pending_writes(pending_writes)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[4]), vec![4]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[2]), vec![0xff]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(scanner.prev().unwrap(), None);
+    }
+
+    /// `limit` stops emitting after `n` keys and short-circuits further cursor work; `offset`
+    /// skips the first `m` emitted keys while still paying their scan cost.
+    #[test]
+    fn test_limit_and_offset() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+
+        // `offset(2)` skips [6] and [5]; `limit(2)` then caps the result at [4] and [3].
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
+            .range(None, None)
+            .offset(2)
+            .limit(2)
+            .build()
+            .unwrap();
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[4]), vec![4]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[3]), vec![3]))
+        );
+        // Drain the stats accumulated while actually emitting the two keys, then confirm the
+        // limit short-circuit that follows adds no further cursor work.
+        scanner.take_statistics();
+        assert_eq!(scanner.prev().unwrap(), None);
+        let statistics = scanner.take_statistics();
+        assert_eq!(statistics.write.prev, 0);
+        assert_eq!(statistics.write.seek, 0);
+        assert_eq!(statistics.write.seek_for_prev, 0);
+
+        // A negative offset keeps only the last `-offset` keys of the range: with both bounds
+        // specified, [1..7) holds six keys, so `offset(-2)` keeps [2] and [1].
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
+            .range(Some(Key::from_raw(&[1])), Some(Key::from_raw(&[7])))
+            .offset(-2)
+            .build()
+            .unwrap();
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[2]), vec![2]))
+        );
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(scanner.prev().unwrap(), None);
+
+        // A negative offset exceeding the range size clamps to keeping the whole range.
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(Some(Key::from_raw(&[1])), Some(Key::from_raw(&[7])))
+            .offset(-100)
+            .build()
+            .unwrap();
+        assert_eq!(
+            scanner.prev().unwrap(),
+            Some((Key::from_raw(&[6]), vec![6]))
+        );
+    }
+
+    /// `WuManber` finds a candidate that sits at the start, middle and end of a haystack, and
+    /// never matches across two haystacks scanned independently.
+    #[test]
+    fn test_wu_manber_find_matches() {
+        let matcher = WuManber::new(vec![b"foo".to_vec(), b"bar".to_vec(), b"bazbaz".to_vec()]);
+
+        assert_eq!(
+            matcher.find_matches(b"foo-middle-bar"),
+            vec![0, 1].into_iter().collect()
+        );
+        assert_eq!(matcher.find_matches(b"nothing here"), BTreeSet::new());
+        // "baz" alone isn't a candidate; only the full "bazbaz" should match.
+        assert_eq!(
+            matcher.find_matches(b"...bazbaz..."),
+            vec![2].into_iter().collect()
+        );
+        // A match split across two independent haystacks must not be found in either.
+        assert_eq!(matcher.find_matches(b"ba"), BTreeSet::new());
+        assert_eq!(matcher.find_matches(b"r"), BTreeSet::new());
+    }
+
+    /// A candidate shorter than the Wu-Manber block size falls back to a direct search rather
+    /// than being silently dropped.
+    #[test]
+    fn test_wu_manber_short_candidate_fallback() {
+        let matcher = WuManber::new(vec![b"a".to_vec(), b"longer".to_vec()]);
+        assert_eq!(
+            matcher.find_matches(b"xxaxx"),
+            vec![0].into_iter().collect()
+        );
+        assert_eq!(
+            matcher.find_matches(b"a longer string"),
+            vec![0, 1].into_iter().collect()
+        );
+    }
+
+    /// `Scanner::scan_values_for_patterns` reports which of a candidate set occur in any
+    /// committed value in range, folding hit counts rather than polluting `Statistics`.
+    #[test]
+    fn test_scan_values_for_patterns() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        must_prewrite_put(&engine, b"k1", b"references:row-42", b"k1", 1);
+        must_commit(&engine, b"k1", 1, 1);
+        must_prewrite_put(&engine, b"k2", b"references:row-42,row-7", b"k2", 1);
+        must_commit(&engine, b"k2", 1, 1);
+        must_prewrite_put(&engine, b"k3", b"no references here", b"k3", 1);
+        must_commit(&engine, b"k3", 1, 1);
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        let mut candidates = BTreeSet::new();
+        candidates.insert(b"row-42".to_vec());
+        candidates.insert(b"row-7".to_vec());
+        candidates.insert(b"row-999".to_vec());
+
+        let matched = scanner.scan_values_for_patterns(candidates).unwrap();
+        assert_eq!(matched, vec![0, 1].into_iter().collect());
+
+        let hits = scanner.take_pattern_hits();
+        assert_eq!(hits.get(&0), Some(&2));
+        assert_eq!(hits.get(&1), Some(&1));
+        assert_eq!(hits.get(&2), None);
+    }
+
+    /// An empty candidate set short-circuits without touching the cursors.
+    #[test]
+    fn test_scan_values_for_patterns_empty_candidates() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+        must_prewrite_put(&engine, b"k1", b"value", b"k1", 1);
+        must_commit(&engine, b"k1", 1, 1);
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .range(None, None)
+            .build()
+            .unwrap();
+
+        let matched = scanner
+            .scan_values_for_patterns(BTreeSet::<Vec<u8>>::new())
+            .unwrap();
+        assert!(matched.is_empty());
+        let statistics = scanner.take_statistics();
+        assert_eq!(statistics.write.next, 0);
+        assert_eq!(statistics.write.seek, 0);
+    }
+
+    /// `ranges` walks several disjoint ranges in one descending pass, tagging each row with its
+    /// originating range index and skipping the gap between them ([3] here) entirely.
+    #[test]
+    fn test_prev_tagged_multi_range() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let mut scanner = ScannerBuilder::new(snapshot, 10)
+            .ranges(vec![
+                (Some(Key::from_raw(&[5])), Some(Key::from_raw(&[7]))),
+                (Some(Key::from_raw(&[1])), Some(Key::from_raw(&[3]))),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((0, Key::from_raw(&[6]), vec![6]))
+        );
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((0, Key::from_raw(&[5]), vec![5]))
+        );
+        // [4] and [3] sit in the gap between the two configured ranges and are never emitted.
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((1, Key::from_raw(&[2]), vec![2]))
+        );
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((1, Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(scanner.prev_tagged().unwrap(), None);
+    }
+
+    /// `continuation_token`/`continue_from` let a fresh `Scanner` resume a multi-range scan
+    /// mid-way, without re-seeking from the owning range's upper boundary.
+    #[test]
+    fn test_continue_from_token() {
+        let engine = engine::new_local_engine(TEMP_DIR, ALL_CFS).unwrap();
+
+        for i in 1..7 {
+            must_prewrite_put(&engine, &[i], &[i], &[i], 1);
+            must_commit(&engine, &[i], 1, 1);
+        }
+
+        let snapshot = engine.snapshot(&Context::new()).unwrap();
+        let ranges = vec![
+            (Some(Key::from_raw(&[5])), Some(Key::from_raw(&[7]))),
+            (Some(Key::from_raw(&[1])), Some(Key::from_raw(&[3]))),
+        ];
+
+        // No position to resume from before the first row is returned.
+        let mut scanner = ScannerBuilder::new(snapshot.clone(), 10)
+            .ranges(ranges.clone())
+            .build()
+            .unwrap();
+        assert_eq!(scanner.continuation_token(), None);
+
+        // Stop after a row budget of 2.
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((0, Key::from_raw(&[6]), vec![6]))
+        );
+        assert_eq!(
+            scanner.prev_tagged().unwrap(),
+            Some((0, Key::from_raw(&[5]), vec![5]))
+        );
+        let token = scanner.continuation_token().unwrap();
+
+        // A freshly built scanner resumes exactly where the first one left off.
+        let mut resumed = ScannerBuilder::new(snapshot, 10)
+            .ranges(ranges)
+            .build()
+            .unwrap();
+        resumed.continue_from(&token).unwrap();
+        assert_eq!(
+            resumed.prev_tagged().unwrap(),
+            Some((1, Key::from_raw(&[2]), vec![2]))
+        );
+        assert_eq!(
+            resumed.prev_tagged().unwrap(),
+            Some((1, Key::from_raw(&[1]), vec![1]))
+        );
+        assert_eq!(resumed.prev_tagged().unwrap(), None);
+    }
 }